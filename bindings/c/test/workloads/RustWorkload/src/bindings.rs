@@ -1,4 +1,4 @@
-use std::{ffi, str::FromStr};
+use std::{ffi, future::Future, str::FromStr};
 
 mod raw_bindings {
     #![allow(non_camel_case_types)]
@@ -7,15 +7,29 @@ mod raw_bindings {
     #![allow(dead_code)]
     include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 }
+mod executor;
+mod future;
+mod histogram;
+mod options;
+mod rng;
+
+pub use future::FdbFuture;
+pub use histogram::Histogram;
+pub use options::OptionsError;
+pub use rng::{ClientRng, ClusterRng};
 pub use raw_bindings::{
     FDBDatabase, FDBMetrics, FDBPromise, FDBWorkload, FDBWorkloadContext, OpaqueWorkload,
 };
 use raw_bindings::{
+    fdb_database_create_transaction, fdb_future_get_error, fdb_future_get_value, fdb_get_error,
+    fdb_transaction_commit, fdb_transaction_destroy, fdb_transaction_get, fdb_transaction_set,
     FDBMetric, FDBSeverity, FDBSeverity_FDBSeverity_Debug, FDBSeverity_FDBSeverity_Error,
     FDBSeverity_FDBSeverity_Info, FDBSeverity_FDBSeverity_Warn, FDBSeverity_FDBSeverity_WarnAlways,
-    FDBStringPair,
+    FDBStringPair, FDBTransaction,
 };
 
+use executor::Executor;
+
 // -----------------------------------------------------------------------------
 // String conversions
 
@@ -34,10 +48,37 @@ where
 // -----------------------------------------------------------------------------
 // Rust Types
 
-pub struct WorkloadContext(FDBWorkloadContext);
+pub struct WorkloadContext {
+    raw: FDBWorkloadContext,
+    executor: Executor,
+}
 pub struct Promise(FDBPromise);
 pub struct Metrics(FDBMetrics);
 
+/// An error code returned by the FoundationDB client library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Error(i32);
+
+impl Error {
+    pub(crate) fn from_code(code: i32) -> Result<(), Self> {
+        if code == 0 {
+            Ok(())
+        } else {
+            Err(Self(code))
+        }
+    }
+    /// The raw `fdb_error_t` code.
+    pub fn code(&self) -> i32 {
+        self.0
+    }
+}
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", str_from_c(unsafe { fdb_get_error(self.0) }))
+    }
+}
+impl std::error::Error for Error {}
+
 /// A single metric entry
 pub struct Metric {
     /// The name of the metric
@@ -71,7 +112,7 @@ pub enum Severity {
 
 impl WorkloadContext {
     pub(crate) fn new(raw: FDBWorkloadContext) -> Self {
-        Self(raw)
+        Self { raw, executor: Executor::default() }
     }
 
     /// Add a log entry in the FoundationDB logs
@@ -96,8 +137,8 @@ impl WorkloadContext {
             })
             .collect::<Vec<_>>();
         unsafe {
-            self.0.trace.unwrap_unchecked()(
-                self.0.inner,
+            self.raw.trace.unwrap_unchecked()(
+                self.raw.inner,
                 severity as FDBSeverity,
                 name.as_ptr(),
                 details.as_ptr(),
@@ -107,19 +148,19 @@ impl WorkloadContext {
     }
     /// Get the process id of the workload
     pub fn get_process_id(&self) -> u64 {
-        unsafe { self.0.getProcessID.unwrap_unchecked()(self.0.inner) }
+        unsafe { self.raw.getProcessID.unwrap_unchecked()(self.raw.inner) }
     }
     /// Set the process id of the workload
     pub fn set_process_id(&self, id: u64) {
-        unsafe { self.0.setProcessID.unwrap_unchecked()(self.0.inner, id) }
+        unsafe { self.raw.setProcessID.unwrap_unchecked()(self.raw.inner, id) }
     }
     /// Get the current time
     pub fn now(&self) -> f64 {
-        unsafe { self.0.now.unwrap_unchecked()(self.0.inner) }
+        unsafe { self.raw.now.unwrap_unchecked()(self.raw.inner) }
     }
     /// Get a determinist 32-bit random number
     pub fn rnd(&self) -> u32 {
-        unsafe { self.0.rnd.unwrap_unchecked()(self.0.inner) }
+        unsafe { self.raw.rnd.unwrap_unchecked()(self.raw.inner) }
     }
     /// Get the value of a parameter from the simulation config file
     ///
@@ -131,12 +172,26 @@ impl WorkloadContext {
         self.get_option_raw(name)
             .and_then(|value| value.parse::<T>().ok())
     }
-    fn get_option_raw(&self, name: &str) -> Option<String> {
+    /// Deserialize the workload's simulation parameters into `T` in one
+    /// call, one `get_option_raw` per field. Fields absent from the
+    /// config fall back to `#[serde(default)]`; a required field that is
+    /// absent traces at [`Severity::Error`] before the error is
+    /// returned. Enum fields match the raw value against the variant
+    /// name; nested struct/map fields expect their raw value to be a
+    /// JSON object, since they have no flat option of their own. See
+    /// [`options::OptionsError`].
+    pub fn parse_options<T>(&self) -> Result<T, options::OptionsError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        options::parse(self)
+    }
+    pub(crate) fn get_option_raw(&self, name: &str) -> Option<String> {
         let null = "";
         let name = str_for_c(name);
         let default_value = str_for_c(null);
         let raw_value = unsafe {
-            self.0.getOption.unwrap_unchecked()(self.0.inner, name.as_ptr(), default_value.as_ptr())
+            self.raw.getOption.unwrap_unchecked()(self.raw.inner, name.as_ptr(), default_value.as_ptr())
         };
         let value = str_from_c(raw_value.inner);
         unsafe { raw_value.free.unwrap_unchecked()(raw_value.inner) };
@@ -148,15 +203,32 @@ impl WorkloadContext {
     }
     /// Get the client id of the workload
     pub fn client_id(&self) -> i32 {
-        unsafe { self.0.clientId.unwrap_unchecked()(self.0.inner) }
+        unsafe { self.raw.clientId.unwrap_unchecked()(self.raw.inner) }
     }
     /// Get the client id of the workload
     pub fn client_count(&self) -> i32 {
-        unsafe { self.0.clientCount.unwrap_unchecked()(self.0.inner) }
+        unsafe { self.raw.clientCount.unwrap_unchecked()(self.raw.inner) }
     }
     /// Get a determinist 64-bit random number
     pub fn shared_random_number(&self) -> i64 {
-        unsafe { self.0.sharedRandomNumber.unwrap_unchecked()(self.0.inner) }
+        unsafe { self.raw.sharedRandomNumber.unwrap_unchecked()(self.raw.inner) }
+    }
+    /// Queue an async task to run alongside the workload on its
+    /// simulation thread. It makes progress whenever this context's
+    /// executor is driven, which happens while a [`Promise`] obtained
+    /// through [`Promise::fulfill_with`] is still pending.
+    pub fn spawn(&self, future: impl Future<Output = ()> + 'static) {
+        self.executor.spawn(future)
+    }
+    /// A deterministic RNG for this client, usable with the `rand`
+    /// ecosystem. See [`ClientRng`].
+    pub fn rng(&self) -> ClientRng<'_> {
+        ClientRng::new(self)
+    }
+    /// A deterministic RNG shared by every client in the cluster. See
+    /// [`ClusterRng`].
+    pub fn cluster_rng(&self) -> ClusterRng<'_> {
+        ClusterRng::new(self)
     }
 }
 
@@ -171,6 +243,32 @@ impl Promise {
     pub fn send(self, value: bool) {
         unsafe { self.0.send.unwrap_unchecked()(self.0.inner, value) };
     }
+    /// Run `future` to completion on `ctx`'s executor, then resolve this
+    /// promise: `true` on success, or `false` after tracing the error at
+    /// [`Severity::Error`].
+    ///
+    /// This is the bridge between `setup`/`start`/`check`, which signal
+    /// completion by resolving a `Promise`, and workload code that wants
+    /// to `.await` FoundationDB operations instead of blocking.
+    pub fn fulfill_with<F>(self, ctx: &WorkloadContext, future: F)
+    where
+        F: Future<Output = Result<(), Error>> + 'static,
+    {
+        let outcome = std::sync::Arc::new(std::sync::Mutex::new(None));
+        let done = outcome.clone();
+        ctx.executor.spawn(async move {
+            *done.lock().unwrap() = Some(future.await);
+        });
+        ctx.executor.run_until(|| outcome.lock().unwrap().is_some());
+        match outcome.lock().unwrap().take() {
+            Some(Ok(())) => self.send(true),
+            Some(Err(err)) => {
+                ctx.trace(Severity::Error, "WorkloadFutureError", &[("error", &err.to_string())]);
+                self.send(false);
+            }
+            None => unreachable!("run_until only returns once outcome is set"),
+        }
+    }
 }
 impl Drop for Promise {
     fn drop(&mut self) {
@@ -200,6 +298,17 @@ impl Metrics {
             )
         }
     }
+    /// Expand a [`Histogram`] into `push` calls for its p50/p90/p99,
+    /// min, max, and count, each reported under a `"{name} <suffix>"`
+    /// key.
+    pub fn push_histogram(&mut self, name: &str, histogram: &Histogram) {
+        self.push(Metric::val(format!("{name} p50"), histogram.quantile(0.50)));
+        self.push(Metric::val(format!("{name} p90"), histogram.quantile(0.90)));
+        self.push(Metric::val(format!("{name} p99"), histogram.quantile(0.99)));
+        self.push(Metric::val(format!("{name} min"), histogram.min()));
+        self.push(Metric::val(format!("{name} max"), histogram.max()));
+        self.push(Metric::val(format!("{name} count"), histogram.count() as f64));
+    }
 }
 
 impl Metric {
@@ -229,4 +338,65 @@ impl Metric {
             fmt: None,
         }
     }
+}
+
+// -----------------------------------------------------------------------------
+// FoundationDB access
+
+/// A FoundationDB database handle, as exported to the workload.
+pub struct Database(*mut FDBDatabase);
+
+impl Database {
+    pub(crate) fn new(raw: *mut FDBDatabase) -> Self {
+        Self(raw)
+    }
+    /// Start a new transaction against this database.
+    pub fn create_transaction(&self) -> Result<Transaction, Error> {
+        let mut raw: *mut FDBTransaction = std::ptr::null_mut();
+        Error::from_code(unsafe { fdb_database_create_transaction(self.0, &mut raw) })?;
+        Ok(Transaction(raw))
+    }
+}
+
+/// A FoundationDB transaction. Reads and the final commit resolve as
+/// [`FdbFuture`]s, so workload code awaits them instead of blocking the
+/// simulation thread.
+pub struct Transaction(*mut FDBTransaction);
+
+impl Transaction {
+    /// Read the value of `key`, if it is set. The returned future
+    /// borrows this transaction, so it cannot outlive it.
+    pub fn get<'a>(&'a self, key: &[u8]) -> impl Future<Output = Result<Option<Vec<u8>>, Error>> + 'a {
+        let raw = unsafe { fdb_transaction_get(self.0, key.as_ptr(), key.len() as i32, 0) };
+        let future = FdbFuture::new(raw, |raw| {
+            let mut present = 0;
+            let mut value = std::ptr::null();
+            let mut value_len = 0;
+            Error::from_code(unsafe { fdb_future_get_value(raw, &mut present, &mut value, &mut value_len) })?;
+            Ok((present != 0)
+                .then(|| unsafe { std::slice::from_raw_parts(value, value_len as usize).to_vec() }))
+        });
+        async move { future.await }
+    }
+    /// Set `key` to `value` in this transaction.
+    pub fn set(&self, key: &[u8], value: &[u8]) {
+        unsafe {
+            fdb_transaction_set(self.0, key.as_ptr(), key.len() as i32, value.as_ptr(), value.len() as i32)
+        };
+    }
+    /// Commit this transaction, consuming it. The transaction stays
+    /// alive until the returned future resolves.
+    pub fn commit(self) -> impl Future<Output = Result<(), Error>> {
+        let raw = unsafe { fdb_transaction_commit(self.0) };
+        let future = FdbFuture::new(raw, |raw| Error::from_code(unsafe { fdb_future_get_error(raw) }));
+        async move {
+            let _transaction = self;
+            future.await
+        }
+    }
+}
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        unsafe { fdb_transaction_destroy(self.0) };
+    }
 }
\ No newline at end of file
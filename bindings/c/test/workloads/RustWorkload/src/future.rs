@@ -0,0 +1,74 @@
+use std::ffi::c_void;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use super::raw_bindings::{fdb_future_destroy, fdb_future_is_ready, fdb_future_set_callback, FDBFuture};
+use super::Error;
+
+#[derive(Default)]
+struct Shared {
+    waker: Mutex<Option<Waker>>,
+}
+
+/// Bridges a raw `FDBFuture*` to a Rust [`Future`].
+///
+/// `FDBFuture` signals readiness through a "set callback" hook rather
+/// than a `Waker`, and that callback fires on FoundationDB's network
+/// thread. `poll` first checks `fdb_future_is_ready` (no callback needed
+/// once the value is already there); otherwise it stashes the current
+/// `Waker` behind a mutex, registers the callback on first poll only,
+/// and the callback — which may run concurrently with a later `poll` —
+/// takes the waker back out and wakes it.
+///
+/// `fdb_future_is_ready`/`fdb_future_set_callback`/`fdb_future_destroy`
+/// are free functions over a real `FDBFuture*`, not swappable per test
+/// the way [`super::WorkloadContext`]'s function-pointer fields are, so
+/// there's no unit test here exercising the callback itself. The
+/// `Mutex<Option<Waker>>` handoff this type relies on is the same
+/// pattern `executor.rs`'s tests drive directly with a manually-woken
+/// future, which is where that logic is actually covered.
+pub struct FdbFuture<T> {
+    inner: *mut FDBFuture,
+    shared: Arc<Shared>,
+    extract: fn(*mut FDBFuture) -> Result<T, Error>,
+}
+
+impl<T> FdbFuture<T> {
+    pub(crate) fn new(inner: *mut FDBFuture, extract: fn(*mut FDBFuture) -> Result<T, Error>) -> Self {
+        Self { inner, shared: Arc::new(Shared::default()), extract }
+    }
+}
+
+unsafe extern "C" fn ready_callback(_future: *mut FDBFuture, parameter: *mut c_void) {
+    let shared = Arc::from_raw(parameter as *const Shared);
+    if let Some(waker) = shared.waker.lock().unwrap().take() {
+        waker.wake();
+    }
+}
+
+impl<T> Future for FdbFuture<T> {
+    type Output = Result<T, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if unsafe { fdb_future_is_ready(self.inner) } == 0 {
+            let mut waker = self.shared.waker.lock().unwrap();
+            let needs_callback = waker.is_none();
+            *waker = Some(cx.waker().clone());
+            drop(waker);
+            if needs_callback {
+                let parameter = Arc::into_raw(self.shared.clone()) as *mut c_void;
+                unsafe { fdb_future_set_callback(self.inner, Some(ready_callback), parameter) };
+            }
+            return Poll::Pending;
+        }
+        Poll::Ready((self.extract)(self.inner))
+    }
+}
+
+impl<T> Drop for FdbFuture<T> {
+    fn drop(&mut self) {
+        unsafe { fdb_future_destroy(self.inner) };
+    }
+}
@@ -0,0 +1,105 @@
+const BUCKET_COUNT: usize = 64;
+
+/// Bounded-memory sketch for recording a latency/size distribution
+/// during `start` and reporting approximate quantiles at `getMetrics`
+/// time, via [`super::Metrics::push_histogram`].
+///
+/// Samples fall into one of [`BUCKET_COUNT`] exponentially-sized
+/// buckets (bucket `0` covers `[0, 1]`, bucket `i > 0` covers
+/// `(2^(i-1), 2^i]`, and the last bucket also takes every larger
+/// sample), so [`Histogram::record`] is O(1) and memory stays
+/// fixed no matter how many samples are taken. Quantiles are reported as
+/// the upper bound of the bucket holding the requested rank, which is
+/// precise enough for dashboards without keeping every sample around.
+pub struct Histogram {
+    buckets: [u64; BUCKET_COUNT],
+    count: u64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self { buckets: [0; BUCKET_COUNT], count: 0, min: f64::INFINITY, max: f64::NEG_INFINITY }
+    }
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one non-negative sample.
+    pub fn record(&mut self, value: f64) {
+        let bucket = if value <= 1.0 { 0 } else { (value.log2().ceil() as usize).min(BUCKET_COUNT - 1) };
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+    pub fn min(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.min }
+    }
+    pub fn max(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.max }
+    }
+
+    /// Approximate the value at quantile `q` (`0.0..=1.0`) as the upper
+    /// bound of the bucket holding that rank.
+    pub fn quantile(&self, q: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let target = ((q * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, &bucket_count) in self.buckets.iter().enumerate() {
+            seen += bucket_count;
+            if seen >= target {
+                return if bucket == 0 { 1.0 } else { 2f64.powi(bucket as i32) };
+            }
+        }
+        self.max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Histogram;
+
+    fn histogram_of(samples: &[f64]) -> Histogram {
+        let mut histogram = Histogram::new();
+        for &sample in samples {
+            histogram.record(sample);
+        }
+        histogram
+    }
+
+    #[test]
+    fn quantile_zero_resolves_against_the_lowest_bucket() {
+        // Regression test: `target` used to truncate to 0 for q == 0.0,
+        // which matched trivially on the first (empty) bucket and
+        // returned its upper bound (1.0) no matter what the samples
+        // were.
+        let histogram = histogram_of(&[1000.0; 10]);
+        assert_eq!(histogram.quantile(0.0), 1024.0);
+    }
+
+    #[test]
+    fn quantile_one_is_the_maximum_bucket() {
+        let histogram = histogram_of(&[1.0, 2.0, 1000.0]);
+        assert_eq!(histogram.quantile(1.0), 1024.0);
+    }
+
+    #[test]
+    fn empty_histogram_reports_zero() {
+        let histogram = Histogram::new();
+        assert_eq!(histogram.count(), 0);
+        assert_eq!(histogram.min(), 0.0);
+        assert_eq!(histogram.max(), 0.0);
+        assert_eq!(histogram.quantile(0.5), 0.0);
+    }
+}
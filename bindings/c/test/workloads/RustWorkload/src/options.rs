@@ -0,0 +1,317 @@
+use std::fmt;
+
+use serde::de::{
+    self, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, Visitor,
+};
+
+use super::{Severity, WorkloadContext};
+
+/// Error returned by [`WorkloadContext::parse_options`].
+#[derive(Debug)]
+pub struct OptionsError(String);
+
+impl fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+impl std::error::Error for OptionsError {}
+impl de::Error for OptionsError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+pub(crate) fn parse<T>(ctx: &WorkloadContext) -> Result<T, OptionsError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    T::deserialize(OptionsDeserializer { ctx }).map_err(|err| {
+        ctx.trace(Severity::Error, "WorkloadOptionsParseError", &[("error", &err.0)]);
+        err
+    })
+}
+
+/// Top-level deserializer: only knows how to deserialize a struct, by
+/// fetching one option per field name through `get_option_raw`.
+struct OptionsDeserializer<'a> {
+    ctx: &'a WorkloadContext,
+}
+
+impl<'de> Deserializer<'de> for OptionsDeserializer<'_> {
+    type Error = OptionsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("workload options can only be deserialized into a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(FieldAccess { ctx: self.ctx, fields: fields.iter(), value: None })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Walks a struct's field names, skipping any whose option is absent
+/// from the config so that `#[serde(default)]` (or a missing-field
+/// error, for required fields) kicks in exactly as it would for any
+/// other `serde::Deserializer`.
+struct FieldAccess<'a> {
+    ctx: &'a WorkloadContext,
+    fields: std::slice::Iter<'static, &'static str>,
+    value: Option<String>,
+}
+
+impl<'de> MapAccess<'de> for FieldAccess<'_> {
+    type Error = OptionsError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        for field in self.fields.by_ref() {
+            if let Some(value) = self.ctx.get_option_raw(field) {
+                self.value = Some(value);
+                return seed.deserialize((*field).into_deserializer()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+        let value = self.value.take().expect("next_value_seed called before next_key_seed");
+        seed.deserialize(ValueDeserializer(value))
+    }
+}
+
+/// Deserializes a single raw option value, parsed on demand into
+/// whatever scalar, string, or enum variant the target field expects.
+/// A field whose type is itself a struct or map has no flat option of
+/// its own to parse a scalar from, so its raw value is expected to hold
+/// a JSON object instead (see `deserialize_struct`/`deserialize_map`).
+struct ValueDeserializer(String);
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident: $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                let parsed = self.0.parse::<$ty>().map_err(|_| {
+                    de::Error::custom(format!("invalid value `{}` for {}", self.0, stringify!($ty)))
+                })?;
+                visitor.$visit(parsed)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = OptionsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool: bool,
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    /// A nested struct field has no single flat option of its own, so
+    /// its raw value is expected to be a JSON object rather than a
+    /// plain scalar; parse it with `serde_json` and hand the visitor
+    /// off to that.
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        serde::Deserializer::deserialize_struct(&mut serde_json::Deserializer::from_reader(self.0.as_bytes()), name, fields, visitor)
+            .map_err(|err| de::Error::custom(format!("invalid nested value `{}`: {}", self.0, err)))
+    }
+
+    /// See [`ValueDeserializer::deserialize_struct`]; a map field is the
+    /// same situation.
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        serde::Deserializer::deserialize_map(&mut serde_json::Deserializer::from_reader(self.0.as_bytes()), visitor)
+            .map_err(|err| de::Error::custom(format!("invalid nested value `{}`: {}", self.0, err)))
+    }
+
+    serde::forward_to_deserialize_any! {
+        string bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct identifier ignored_any char
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::ffi::{c_void, CStr, CString};
+
+    use serde::Deserialize;
+
+    use crate::bindings::raw_bindings::{FDBOptionValue, FDBSeverity, FDBStringPair, FDBWorkloadContext};
+    use crate::bindings::WorkloadContext;
+
+    /// Stands in for the simulation's C++ workload context: `getOption`
+    /// is backed by a name -> value map that's drained as options are
+    /// read, matching the real API's "getting an option consumes it"
+    /// behavior; `trace` is a no-op so a parse error can be traced
+    /// without a real simulation to receive it.
+    struct MockState {
+        options: RefCell<HashMap<String, String>>,
+    }
+
+    fn cstr_arg(ptr: *const i8) -> String {
+        unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned()
+    }
+
+    unsafe extern "C" fn mock_get_option(
+        inner: *mut c_void,
+        name: *const i8,
+        default_value: *const i8,
+    ) -> FDBOptionValue {
+        let state = &*(inner as *const MockState);
+        let name = cstr_arg(name);
+        let value = state
+            .options
+            .borrow_mut()
+            .remove(&name)
+            .unwrap_or_else(|| cstr_arg(default_value));
+        FDBOptionValue { inner: CString::new(value).unwrap().into_raw(), free: Some(mock_free_option) }
+    }
+
+    unsafe extern "C" fn mock_free_option(ptr: *const i8) {
+        drop(CString::from_raw(ptr as *mut i8));
+    }
+
+    unsafe extern "C" fn mock_trace(
+        _inner: *mut c_void,
+        _severity: FDBSeverity,
+        _name: *const i8,
+        _details: *const FDBStringPair,
+        _details_len: i32,
+    ) {
+    }
+
+    fn mock_context(options: &[(&str, &str)]) -> (WorkloadContext, Box<MockState>) {
+        let state = Box::new(MockState {
+            options: RefCell::new(options.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()),
+        });
+        let raw = FDBWorkloadContext {
+            inner: &*state as *const MockState as *mut c_void,
+            trace: Some(mock_trace),
+            getProcessID: None,
+            setProcessID: None,
+            now: None,
+            rnd: None,
+            getOption: Some(mock_get_option),
+            clientId: None,
+            clientCount: None,
+            sharedRandomNumber: None,
+        };
+        (WorkloadContext::new(raw), state)
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        count: u32,
+        #[serde(default = "default_timeout")]
+        timeout: f64,
+        mode: Mode,
+        #[serde(default)]
+        retry: Retry,
+    }
+
+    fn default_timeout() -> f64 {
+        5.0
+    }
+
+    #[derive(Deserialize, Debug, PartialEq, Default)]
+    struct Retry {
+        #[serde(default)]
+        attempts: u32,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Mode {
+        Read,
+        Write,
+    }
+
+    #[test]
+    fn missing_field_falls_back_to_serde_default() {
+        let (ctx, _state) = mock_context(&[("count", "3"), ("mode", "write")]);
+        let config: Config = ctx.parse_options().unwrap();
+        assert_eq!(config, Config { count: 3, timeout: 5.0, mode: Mode::Write, retry: Retry::default() });
+    }
+
+    #[test]
+    fn present_field_overrides_default() {
+        let (ctx, _state) = mock_context(&[("count", "3"), ("timeout", "1.5"), ("mode", "read")]);
+        let config: Config = ctx.parse_options().unwrap();
+        assert_eq!(config.timeout, 1.5);
+        assert_eq!(config.mode, Mode::Read);
+    }
+
+    #[test]
+    fn required_field_missing_is_an_error() {
+        let (ctx, _state) = mock_context(&[("mode", "read")]);
+        let err = ctx.parse_options::<Config>().unwrap_err();
+        assert!(err.to_string().contains("count"));
+    }
+
+    #[test]
+    fn invalid_scalar_value_is_an_error() {
+        let (ctx, _state) = mock_context(&[("count", "not a number"), ("mode", "read")]);
+        assert!(ctx.parse_options::<Config>().is_err());
+    }
+
+    #[test]
+    fn unknown_enum_variant_is_an_error() {
+        let (ctx, _state) = mock_context(&[("count", "1"), ("mode", "delete")]);
+        assert!(ctx.parse_options::<Config>().is_err());
+    }
+
+    #[test]
+    fn nested_struct_field_is_parsed_as_json() {
+        let (ctx, _state) =
+            mock_context(&[("count", "1"), ("mode", "read"), ("retry", r#"{"attempts": 7}"#)]);
+        let config: Config = ctx.parse_options().unwrap();
+        assert_eq!(config.retry, Retry { attempts: 7 });
+    }
+}
@@ -0,0 +1,175 @@
+use rand::RngCore;
+
+use super::WorkloadContext;
+
+/// Deterministic RNG backed by [`WorkloadContext::rnd`], usable with the
+/// whole `rand` ecosystem (`gen_range`, `choose`, shuffles, weighted
+/// sampling, ...) without breaking FoundationDB's simulation replay
+/// guarantees. Each client draws its own sequence.
+pub struct ClientRng<'a> {
+    ctx: &'a WorkloadContext,
+}
+
+impl<'a> ClientRng<'a> {
+    pub(crate) fn new(ctx: &'a WorkloadContext) -> Self {
+        Self { ctx }
+    }
+}
+
+impl RngCore for ClientRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        self.ctx.rnd()
+    }
+    fn next_u64(&mut self) -> u64 {
+        let hi = self.next_u32() as u64;
+        let lo = self.next_u32() as u64;
+        (hi << 32) | lo
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            chunk.copy_from_slice(&self.next_u32().to_le_bytes()[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Deterministic RNG backed by [`WorkloadContext::shared_random_number`],
+/// so every client in the cluster observes the same sequence of draws.
+/// Useful when clients need to agree on something (a shared key range, a
+/// coordinator, ...) without communicating.
+pub struct ClusterRng<'a> {
+    ctx: &'a WorkloadContext,
+    /// The high 32 bits of a `shared_random_number()` draw not yet
+    /// handed out by `next_u32`, so a single 64-bit draw backs two
+    /// `next_u32` calls instead of discarding half its entropy.
+    cached_high: Option<u32>,
+}
+
+impl<'a> ClusterRng<'a> {
+    pub(crate) fn new(ctx: &'a WorkloadContext) -> Self {
+        Self { ctx, cached_high: None }
+    }
+}
+
+impl RngCore for ClusterRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        if let Some(high) = self.cached_high.take() {
+            return high;
+        }
+        let value = self.ctx.shared_random_number() as u64;
+        self.cached_high = Some((value >> 32) as u32);
+        value as u32
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.cached_high = None;
+        self.ctx.shared_random_number() as u64
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            chunk.copy_from_slice(&self.next_u32().to_le_bytes()[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::ffi::c_void;
+
+    use rand::RngCore;
+
+    use super::{ClientRng, ClusterRng};
+    use crate::bindings::raw_bindings::FDBWorkloadContext;
+    use crate::bindings::WorkloadContext;
+
+    /// Stands in for the simulation's C++ workload context, yielding a
+    /// fixed, pre-recorded sequence of draws from `rnd()` /
+    /// `shared_random_number()` so the RNG adapters can be tested
+    /// without a real FoundationDB simulation running.
+    struct MockState {
+        rnd_values: Vec<u32>,
+        rnd_index: Cell<usize>,
+        shared_values: Vec<i64>,
+        shared_index: Cell<usize>,
+    }
+
+    unsafe extern "C" fn mock_rnd(inner: *mut c_void) -> u32 {
+        let state = &*(inner as *const MockState);
+        let i = state.rnd_index.get();
+        state.rnd_index.set(i + 1);
+        state.rnd_values[i]
+    }
+
+    unsafe extern "C" fn mock_shared_random_number(inner: *mut c_void) -> i64 {
+        let state = &*(inner as *const MockState);
+        let i = state.shared_index.get();
+        state.shared_index.set(i + 1);
+        state.shared_values[i]
+    }
+
+    fn mock_context(rnd_values: Vec<u32>, shared_values: Vec<i64>) -> (WorkloadContext, Box<MockState>) {
+        let state = Box::new(MockState {
+            rnd_values,
+            rnd_index: Cell::new(0),
+            shared_values,
+            shared_index: Cell::new(0),
+        });
+        let raw = FDBWorkloadContext {
+            inner: &*state as *const MockState as *mut c_void,
+            trace: None,
+            getProcessID: None,
+            setProcessID: None,
+            now: None,
+            rnd: Some(mock_rnd),
+            getOption: None,
+            clientId: None,
+            clientCount: None,
+            sharedRandomNumber: Some(mock_shared_random_number),
+        };
+        (WorkloadContext::new(raw), state)
+    }
+
+    #[test]
+    fn client_rng_next_u64_composes_two_rnd_calls() {
+        let (ctx, _state) = mock_context(vec![0x1111_2222, 0x3333_4444], vec![]);
+        let mut rng = ClientRng::new(&ctx);
+        assert_eq!(rng.next_u64(), 0x1111_2222_3333_4444);
+    }
+
+    #[test]
+    fn client_rng_fill_bytes_slices_the_tail_chunk() {
+        let (ctx, _state) = mock_context(vec![0x0403_0201, 0x0007_0605], vec![]);
+        let mut rng = ClientRng::new(&ctx);
+        let mut dest = [0u8; 6];
+        rng.fill_bytes(&mut dest);
+        assert_eq!(dest, [0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+    }
+
+    #[test]
+    fn cluster_rng_next_u32_splits_one_shared_draw_across_two_calls() {
+        let (ctx, _state) = mock_context(vec![], vec![0x1111_2222_3333_4444u64 as i64]);
+        let mut rng = ClusterRng::new(&ctx);
+        assert_eq!(rng.next_u32(), 0x3333_4444);
+        assert_eq!(rng.next_u32(), 0x1111_2222);
+    }
+
+    #[test]
+    fn cluster_rng_next_u64_discards_any_cached_half_draw() {
+        let (ctx, _state) = mock_context(
+            vec![],
+            vec![0x1111_2222_3333_4444u64 as i64, 0x5555_6666_7777_8888u64 as i64],
+        );
+        let mut rng = ClusterRng::new(&ctx);
+        // Partially consume the first draw, caching its high half...
+        assert_eq!(rng.next_u32(), 0x3333_4444);
+        // ...then a next_u64 call must not return the stale cached half.
+        assert_eq!(rng.next_u64(), 0x5555_6666_7777_8888);
+    }
+}